@@ -20,7 +20,7 @@ fn create_logger() -> IoResult<Json<TcpStream>> {
 }
 
 fn main() {
-    let retry = slog_retry::Retry::new(create_logger, None, true)
+    let retry = slog_retry::Retry::new(create_logger, None, true, None)
         // Kill the application if the initial connection fails
         .unwrap()
         // Ignore if it isn't possible to log some of the messages, we'll try again
@@ -0,0 +1,161 @@
+//! Built-in exponential backoff strategy.
+//!
+//! The default strategy used by [`Retry`](../struct.Retry.html) is a fixed linear schedule, which
+//! is fine for a handful of reconnects but tends to make every client hammer a downed endpoint in
+//! lock-step. This module provides a [`Backoff`](struct.Backoff.html) builder for an exponential
+//! schedule with decorrelated jitter, so retries spread out instead of piling up.
+
+use std::cmp;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use {NewStrategy, Strategy};
+
+/// A tiny linear congruential generator.
+///
+/// This is not meant to be a good source of randomness, just cheap jitter to desynchronize
+/// reconnecting clients. Pulling in a proper RNG crate for that seemed like overkill.
+struct Lcg(u64);
+
+impl Lcg {
+    /// Seeds the generator from the current time.
+    fn seeded() -> Self {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_else(|_| Duration::from_secs(0));
+        let seed = now.as_secs() ^ u64::from(now.subsec_nanos());
+        Lcg(seed)
+    }
+
+    /// Returns the next pseudo-random number, uniformly distributed in `[0, 1)`.
+    fn next_f64(&mut self) -> f64 {
+        // The multiplier and increment of Knuth's MMIX generator.
+        self.0 = self.0
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        (self.0 >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+fn duration_to_nanos(duration: Duration) -> u64 {
+    duration.as_secs()
+        .saturating_mul(1_000_000_000)
+        .saturating_add(u64::from(duration.subsec_nanos()))
+}
+
+fn nanos_to_duration(nanos: u64) -> Duration {
+    Duration::new(nanos / 1_000_000_000, (nanos % 1_000_000_000) as u32)
+}
+
+/// A builder for an exponential-backoff-with-jitter [`NewStrategy`](../type.NewStrategy.html).
+///
+/// It starts at `base` and after every attempt grows (roughly, due to the jitter) towards `max`
+/// by `factor`, for at most `retries` attempts. The jitter uses the "decorrelated jitter"
+/// recurrence (as described by the AWS architecture blog): each delay is picked at random from
+/// `[base, previous * factor]` and then clamped to `max`. That avoids both the thundering herd of
+/// no jitter at all and the unbounded growth of plain exponential backoff.
+///
+/// ```rust
+/// use std::time::Duration;
+/// use slog_retry::Backoff;
+///
+/// let _strategy = Backoff::exponential(
+///     Duration::from_millis(100),
+///     3,
+///     Duration::from_secs(30),
+///     10,
+/// );
+/// ```
+pub struct Backoff {
+    base: Duration,
+    factor: u32,
+    max: Duration,
+    retries: usize,
+}
+
+impl Backoff {
+    /// Creates the builder.
+    ///
+    /// # Parameters
+    ///
+    /// * `base`: The delay before the first retry (and the lower bound of every subsequent one).
+    /// * `factor`: How much the upper bound of the delay grows with each attempt.
+    /// * `max`: The delay is never allowed to grow past this.
+    /// * `retries`: The number of reconnect attempts the produced strategy allows.
+    ///
+    /// Convert the result into a [`NewStrategy`](../type.NewStrategy.html) with `.into()` to pass
+    /// it to [`Retry::new`](../struct.Retry.html#method.new).
+    pub fn exponential(base: Duration, factor: u32, max: Duration, retries: usize) -> Self {
+        Backoff { base, factor, max, retries }
+    }
+}
+
+impl From<Backoff> for NewStrategy {
+    fn from(backoff: Backoff) -> NewStrategy {
+        Box::new(move || -> Strategy {
+            Box::new(Iter {
+                rng: Lcg::seeded(),
+                current: backoff.base,
+                base: backoff.base,
+                factor: backoff.factor,
+                max: backoff.max,
+            }.take(backoff.retries))
+        })
+    }
+}
+
+struct Iter {
+    rng: Lcg,
+    current: Duration,
+    base: Duration,
+    factor: u32,
+    max: Duration,
+}
+
+impl Iterator for Iter {
+    type Item = Duration;
+    fn next(&mut self) -> Option<Duration> {
+        let result = self.current;
+
+        let scaled = self.current.checked_mul(self.factor).unwrap_or(self.max);
+        let upper = cmp::max(cmp::min(self.max, scaled), self.base);
+        let span = duration_to_nanos(upper - self.base);
+        let jittered = (span as f64 * self.rng.next_f64()) as u64;
+        self.current = cmp::min(self.max, self.base + nanos_to_duration(jittered));
+
+        Some(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn yields_exactly_retries_items() {
+        let strategy: NewStrategy = Backoff::exponential(
+            Duration::from_millis(10),
+            2,
+            Duration::from_secs(1),
+            7,
+        ).into();
+        assert_eq!(strategy().count(), 7);
+    }
+
+    #[test]
+    fn stays_within_base_and_max() {
+        let base = Duration::from_millis(10);
+        let max = Duration::from_millis(200);
+        let strategy: NewStrategy = Backoff::exponential(base, 3, max, 50).into();
+        for delay in strategy() {
+            assert!(delay >= base, "{:?} is below the base delay", delay);
+            assert!(delay <= max, "{:?} is above the max delay", delay);
+        }
+    }
+
+    #[test]
+    fn first_delay_is_base() {
+        let base = Duration::from_millis(25);
+        let strategy: NewStrategy = Backoff::exponential(base, 2, Duration::from_secs(1), 3).into();
+        assert_eq!(strategy().next(), Some(base));
+    }
+}
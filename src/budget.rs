@@ -0,0 +1,112 @@
+//! A token-bucket budget limiting the rate of reconnection attempts.
+//!
+//! Without one, a sustained backend outage makes [`Retry`](../struct.Retry.html) re-run a full
+//! [strategy](../type.Strategy.html) for every single log record, multiplying the number of
+//! reconnection attempts with the log volume. A [`Budget`](struct.Budget.html) caps that: each
+//! reconnection attempt withdraws a token and each successful log deposits a fraction of one, so
+//! a logger that's mostly succeeding can still afford a burst of retries, while one that's mostly
+//! failing is cut off quickly instead of sleeping through the whole strategy.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+fn duration_to_secs(duration: Duration) -> f64 {
+    duration.as_secs() as f64 + f64::from(duration.subsec_nanos()) / 1_000_000_000.0
+}
+
+/// A token bucket of reconnection attempts.
+///
+/// Wrap it with [`shared`](#method.shared) to use it from one or more
+/// [`Retry::with_budget`](../struct.Retry.html#method.with_budget) adapters pointing at the same
+/// backend.
+pub struct Budget {
+    balance: f64,
+    min_per_sec: f64,
+    retry_percent: f64,
+    last_refill: Instant,
+}
+
+impl Budget {
+    /// Creates a new budget.
+    ///
+    /// # Parameters
+    ///
+    /// * `min_per_sec`: The bucket refills at this many tokens per second no matter how much
+    ///   logging is going on, giving a minimum retry rate even during total silence.
+    /// * `retry_percent`: On top of the steady refill, every successful log deposits this
+    ///   fraction of a token, so a busy, mostly-healthy logger can afford retries proportional to
+    ///   its own traffic.
+    ///
+    /// The bucket starts out with enough balance for at least one attempt.
+    pub fn new(min_per_sec: f64, retry_percent: f64) -> Self {
+        Budget {
+            balance: min_per_sec.max(1.0),
+            min_per_sec,
+            retry_percent,
+            last_refill: Instant::now(),
+        }
+    }
+
+    /// Wraps the budget so it can be shared between several `Retry` adapters.
+    pub fn shared(self) -> Arc<Mutex<Self>> {
+        Arc::new(Mutex::new(self))
+    }
+
+    fn refill(&mut self) {
+        let now = Instant::now();
+        let elapsed = duration_to_secs(now.duration_since(self.last_refill));
+        self.balance += elapsed * self.min_per_sec;
+        self.last_refill = now;
+    }
+
+    /// Deposits the fractional token earned by a successful log.
+    pub(crate) fn deposit(&mut self) {
+        self.refill();
+        self.balance += self.retry_percent;
+    }
+
+    /// Withdraws a token for a reconnection attempt, returning whether there was one to spend.
+    pub(crate) fn withdraw(&mut self) -> bool {
+        self.refill();
+        if self.balance >= 1.0 {
+            self.balance -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A zero min_per_sec keeps these deterministic ‒ otherwise the real-time refill between
+    // assertions would make the expected balances depend on how long the test takes to run.
+
+    #[test]
+    fn starts_with_at_least_one_token() {
+        let mut budget = Budget::new(0.0, 0.5);
+        assert!(budget.withdraw());
+        assert!(!budget.withdraw());
+    }
+
+    #[test]
+    fn deposit_accumulates_towards_a_token() {
+        let mut budget = Budget::new(0.0, 0.5);
+        assert!(budget.withdraw());
+        assert!(!budget.withdraw());
+        budget.deposit();
+        assert!(!budget.withdraw());
+        budget.deposit();
+        assert!(budget.withdraw());
+    }
+
+    #[test]
+    fn shared_budget_is_seen_by_every_handle() {
+        let shared = Budget::new(0.0, 1.0).shared();
+        let other = Arc::clone(&shared);
+        assert!(shared.lock().unwrap().withdraw());
+        assert!(!other.lock().unwrap().withdraw());
+    }
+}
@@ -35,7 +35,7 @@
 //!     let retry = slog_retry::Retry::new(|| -> Result<_, std::io::Error> {
 //!             let connection = TcpStream::connect("127.0.0.1:1234")?;
 //!             Ok(slog_json::Json::default(connection))
-//!         }, None, true)
+//!         }, None, true, None)
 //!         // Kill the application if the initial connection fails
 //!         .unwrap()
 //!         // Ignore if it isn't possible to log some of the messages, we'll try again
@@ -50,15 +50,23 @@
 extern crate failure;
 extern crate slog;
 
-use std::cell::{Cell, RefCell, RefMut};
 use std::fmt::{Debug, Display, Formatter, Result as FmtResult};
 use std::iter;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
 use std::thread;
 use std::time::Duration;
 
 use failure::Fail;
 use slog::{Drain, Record, OwnedKVList};
 
+mod backoff;
+mod budget;
+
+pub use backoff::Backoff;
+pub use budget::Budget;
+
 /// An error when the retry adaptor fails.
 ///
 /// It wasn't possible to log the record (or initialize it when starting). Usually that means it
@@ -118,6 +126,310 @@ pub type Strategy = Box<Iterator<Item = Duration>>;
 /// retry strategy.
 pub type NewStrategy = Box<Fn() -> Strategy + Send>;
 
+/// Which side produced an error during a (re)connection attempt.
+///
+/// This is passed to an optional [`Classifier`](type.Classifier.html) so it can tell apart a
+/// failure to create a new drain from a failure while logging through an existing one.
+pub enum SlaveOrFactoryError<'a, FactoryError: 'a, SlaveError: 'a> {
+    /// The factory failed to produce a new slave drain.
+    Factory(&'a FactoryError),
+    /// An existing slave drain failed to log a record.
+    Slave(&'a SlaveError),
+}
+
+/// What to do about a single (re)connection attempt, as decided by a
+/// [`Classifier`](type.Classifier.html).
+#[derive(Debug)]
+pub enum RetryAction {
+    /// Keep going, waiting as long as the strategy says.
+    Retry,
+    /// Keep going, but wait this long before the next attempt instead of what the strategy says.
+    RetryAfter(Duration),
+    /// Stop retrying right away and report the error, without waiting out the rest of the
+    /// strategy.
+    GiveUpNow,
+    /// Stop retrying right away and report this error, because it is not the kind of error that
+    /// retrying could ever fix (for example a rejected TLS certificate or a bad configuration).
+    ForwardImmediately,
+}
+
+/// A callback deciding how to react to an error during a (re)connection attempt.
+///
+/// It is given the error that just happened and the number of attempts made so far (the first
+/// error is attempt `1`) and decides whether (and how) to keep retrying. Without one, the
+/// adapter always retries according to the [strategy](type.Strategy.html) alone, which is the
+/// previous behaviour.
+pub type Classifier<FactoryError, SlaveError> =
+    Box<Fn(&SlaveOrFactoryError<FactoryError, SlaveError>, usize) -> RetryAction + Send>;
+
+/// A hook invoked before each (re)connection attempt.
+///
+/// It is given the attempt number (starting at `1`) and how long the adapter is about to sleep
+/// before making it.
+pub type OnAttempt = Box<Fn(usize, Duration) + Send>;
+
+/// A hook invoked when a reconnection succeeds.
+///
+/// It is given the number of attempts it took. It does not fire for a connection made by the
+/// adapter's very first connection run (the `connect_now` one, or the one started by the first
+/// logged record). It fires for a success in any later run, even if that first run gave up and
+/// no drain has worked before.
+pub type OnReconnectSuccess = Box<Fn(usize) + Send>;
+
+/// A hook invoked when the adapter gives up retrying and is about to return the error to the
+/// caller.
+pub type OnGiveUp<FactoryError, SlaveError> = Box<Fn(&Error<FactoryError, SlaveError>) + Send>;
+
+/// A liveness check used by the background heartbeat thread (see
+/// [`Retry::with_heartbeat`](struct.Retry.html#method.with_heartbeat)) to tell whether the current
+/// slave drain is still good, or whether it silently died and needs reconnecting.
+///
+/// This adapter has no protocol-level way to probe an arbitrary `Drain` on its own, so the
+/// application supplies the check, for example by peeking at the underlying socket's error state
+/// or sending a protocol-level ping.
+///
+/// Unlike [`NewStrategy`](type.NewStrategy.html) and the other callbacks, which only need to be
+/// `Send`, this must also be `Sync` since it is consulted from the heartbeat thread while the
+/// thread doing the actual logging may be running at the same time.
+pub type LivenessProbe<Slave> = Box<Fn(&Slave) -> bool + Send + Sync>;
+
+/// The state shared between a [`Retry`](struct.Retry.html) and its optional heartbeat thread.
+///
+/// `strategy`, `classifier` and the `on_*` hooks are `Mutex`-wrapped, even though nothing ever
+/// mutates them after construction, purely so `Inner` is `Sync` (a `Mutex<T>` is `Sync` as long as
+/// `T: Send`, regardless of whether `T` itself is `Sync`). That lets the heartbeat thread share
+/// this struct without requiring the application's classifier or hooks to be `Sync`, only `Send`
+/// ‒ the same bound they needed before the heartbeat thread existed.
+struct Inner<Slave, Factory, FactoryError>
+where
+    Slave: Drain,
+    Slave::Err: Fail + Debug,
+    FactoryError: Fail + Debug,
+{
+    slave: Mutex<Option<Slave>>,
+    factory: Factory,
+    strategy: Mutex<NewStrategy>,
+    initialized: AtomicBool,
+    classifier: Mutex<Option<Classifier<FactoryError, Slave::Err>>>,
+    budget: Option<Arc<Mutex<Budget>>>,
+    on_attempt: Mutex<Option<OnAttempt>>,
+    on_reconnect_success: Mutex<Option<OnReconnectSuccess>>,
+    on_give_up: Mutex<Option<OnGiveUp<FactoryError, Slave::Err>>>,
+    liveness_probe: Option<LivenessProbe<Slave>>,
+}
+
+impl<Slave, FactoryError, Factory> Inner<Slave, Factory, FactoryError>
+where
+    Slave: Drain,
+    FactoryError: Fail + Debug,
+    Slave::Err: Fail + Debug,
+    Factory: Fn() -> Result<Slave, FactoryError>,
+{
+    /// Consults the classifier, if any, defaulting to `Retry` when there is none.
+    fn classify(&self, err: &SlaveOrFactoryError<FactoryError, Slave::Err>, attempt: usize)
+        -> RetryAction
+    {
+        self.classifier
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|classify| classify(err, attempt))
+            .unwrap_or(RetryAction::Retry)
+    }
+    /// Deposits the fractional token a successful log earns into the budget, if any.
+    fn deposit(&self) {
+        if let Some(ref budget) = self.budget {
+            budget.lock().unwrap().deposit();
+        }
+    }
+    /// Invokes the give-up hook, if any, just before the caller reports `err` to its caller.
+    fn give_up(&self, err: &Error<FactoryError, Slave::Err>) {
+        if let Some(ref hook) = *self.on_give_up.lock().unwrap() {
+            hook(err);
+        }
+    }
+    /// Runs (a part of) a reconnect strategy, trying to produce a fresh slave.
+    ///
+    /// This only locks `slave` for the brief moment needed to store a freshly created one; the
+    /// sleeps between attempts (and the factory call itself) happen with the mutex unlocked, so a
+    /// `log` call arriving while a reconnect is in progress elsewhere (typically the heartbeat
+    /// thread) isn't blocked for the whole backoff schedule.
+    ///
+    /// `factory_attempt` and `attempt_index` are owned by the caller rather than reset here,
+    /// since one call to [`log`](#method.log) can invoke this more than once (if a freshly
+    /// reconnected slave immediately fails again) and the attempt counts reported to the
+    /// classifier and the `on_*` hooks are meant to keep counting across that, not restart at
+    /// the top of every single call.
+    fn init(
+        &self,
+        slave: &Mutex<Option<Slave>>,
+        strategy: &mut Strategy,
+        factory_attempt: &mut usize,
+        attempt_index: &mut usize,
+    ) -> Result<(), Option<FactoryError>>
+    {
+        let reconnecting = self.initialized.load(Ordering::SeqCst);
+        let prefix: Strategy = if reconnecting {
+            Box::new(iter::empty())
+        } else {
+            self.initialized.store(true, Ordering::SeqCst);
+            Box::new(iter::once(Duration::from_secs(0)))
+        };
+        let mut last_err = None;
+        for mut sleep in prefix.chain(strategy) {
+            if let Some(ref err) = last_err {
+                *factory_attempt += 1;
+                let action = self.classify(&SlaveOrFactoryError::Factory(err), *factory_attempt);
+                match action {
+                    RetryAction::GiveUpNow | RetryAction::ForwardImmediately => {
+                        return Err(last_err);
+                    },
+                    RetryAction::RetryAfter(delay) => sleep = delay,
+                    RetryAction::Retry => {},
+                }
+            }
+            if let Some(ref budget) = self.budget {
+                if !budget.lock().unwrap().withdraw() {
+                    return Err(last_err);
+                }
+            }
+            *attempt_index += 1;
+            if let Some(ref hook) = *self.on_attempt.lock().unwrap() {
+                hook(*attempt_index, sleep);
+            }
+            thread::sleep(sleep);
+            match (self.factory)() {
+                Ok(ok) => {
+                    *slave.lock().unwrap() = Some(ok);
+                    if reconnecting {
+                        if let Some(ref hook) = *self.on_reconnect_success.lock().unwrap() {
+                            hook(*attempt_index);
+                        }
+                    }
+                    return Ok(());
+                },
+                Err(err) => last_err = Some(err),
+            }
+        }
+        Err(last_err)
+    }
+    fn log(&self, record: &Record, values: &OwnedKVList) -> Result<Slave::Ok, Error<FactoryError, Slave::Err>> {
+        let mut slave_err = None;
+        {
+            let borrowed = self.slave.lock().unwrap();
+            if let Some(ref slave) = *borrowed {
+                match slave.log(record, values) {
+                    Ok(ok) => {
+                        drop(borrowed);
+                        self.deposit();
+                        return Ok(ok);
+                    },
+                    Err(err) => slave_err = Some(err),
+                }
+            }
+        }
+        // By now there was no slave to start with or it failed, so we recreate it.
+        *self.slave.lock().unwrap() = None;
+
+        // Try creating a new one and retry with that.
+        let mut strategy = (self.strategy.lock().unwrap())();
+        let mut attempt = 0;
+        let mut factory_attempt = 0;
+        let mut attempt_index = 0;
+        loop {
+            if let Some(ref err) = slave_err {
+                attempt += 1;
+                let action = self.classify(&SlaveOrFactoryError::Slave(err), attempt);
+                match action {
+                    RetryAction::GiveUpNow | RetryAction::ForwardImmediately => {
+                        let err = Error { factory: None, slave: slave_err };
+                        self.give_up(&err);
+                        return Err(err);
+                    },
+                    RetryAction::RetryAfter(delay) => {
+                        // Replace the strategy's next delay with this one, keeping the number of
+                        // attempts the strategy allows.
+                        if strategy.next().is_some() {
+                            strategy = Box::new(iter::once(delay).chain(strategy));
+                        }
+                    },
+                    RetryAction::Retry => {},
+                }
+            }
+            match self.init(&self.slave, &mut strategy, &mut factory_attempt, &mut attempt_index) {
+                Err(factory) => {
+                    let err = Error { factory, slave: slave_err };
+                    self.give_up(&err);
+                    return Err(err);
+                },
+                Ok(()) => {
+                    let borrowed = self.slave.lock().unwrap();
+                    match borrowed.as_ref().unwrap().log(record, values) {
+                        Ok(ok) => {
+                            drop(borrowed);
+                            self.deposit();
+                            return Ok(ok);
+                        },
+                        Err(err) => slave_err = Some(err),
+                    }
+                },
+            }
+        }
+    }
+    /// Reconnects off the hot path if the slave is currently known (or found) to be dead.
+    ///
+    /// This is what backs [`Retry::with_heartbeat`](struct.Retry.html#method.with_heartbeat). A
+    /// slave that failed on the hot path already left the slot empty, so this always reconnects
+    /// it. A slave that is still sitting in the slot is only reconnected if a
+    /// [`LivenessProbe`](type.LivenessProbe.html) was configured and says it is dead; without one,
+    /// this can only notice a connection once something else has already tried and failed to use
+    /// it.
+    fn heartbeat_tick(&self) {
+        let alive = {
+            let borrowed = self.slave.lock().unwrap();
+            match *borrowed {
+                Some(ref slave) => self.liveness_probe
+                    .as_ref()
+                    .map(|probe| probe(slave))
+                    .unwrap_or(true),
+                None => false,
+            }
+        };
+        if alive {
+            return;
+        }
+        *self.slave.lock().unwrap() = None;
+        let mut strategy = (self.strategy.lock().unwrap())();
+        let mut factory_attempt = 0;
+        let mut attempt_index = 0;
+        if let Err(factory) = self.init(&self.slave, &mut strategy, &mut factory_attempt, &mut attempt_index) {
+            let err = Error { factory, slave: None };
+            self.give_up(&err);
+        }
+    }
+}
+
+/// A handle to the optional background heartbeat thread of a [`Retry`](struct.Retry.html).
+///
+/// Dropping it asks the thread to stop and waits for it to do so, so a `Retry` with a heartbeat
+/// shuts the thread down cleanly when it is dropped.
+struct Heartbeat {
+    // Dropped explicitly (before joining) to disconnect the channel and wake the thread's
+    // `recv_timeout` immediately, instead of waiting for it to happen on its own once this whole
+    // struct is dropped after `Drop::drop` returns.
+    shutdown: Option<mpsc::Sender<()>>,
+    handle: Option<thread::JoinHandle<()>>,
+}
+
+impl Drop for Heartbeat {
+    fn drop(&mut self) {
+        self.shutdown.take();
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
 /// The retry adapter.
 ///
 /// This wraps another drain and forwards log records into that. However, if the drain returns an
@@ -130,6 +442,12 @@ pub type NewStrategy = Box<Fn() -> Strategy + Send>;
 /// However, it is not destroyed by the error and if it is called to log another record, it tries
 /// to reconnect again (using a fresh instance of the strategy).
 ///
+/// An optional [classifier](type.Classifier.html) can override that decision on a per-error
+/// basis, for example to give up immediately on an error that will never go away on its own.
+/// Optional [`on_attempt`](#method.on_attempt), [`on_reconnect_success`](#method.on_reconnect_success)
+/// and [`on_give_up`](#method.on_give_up) hooks let the application observe that behavior instead
+/// of it happening silently.
+///
 /// # Warning
 ///
 /// This adapter is *synchronous* and *blocks* during the retry attempts. Unless you provide a
@@ -137,14 +455,17 @@ pub type NewStrategy = Box<Fn() -> Strategy + Send>;
 /// [slog-async](https://crates.io/crates/slog-async), where it'll only slow down the logging
 /// thread and the channel into that thread will be used as a buffer for messages waiting to be
 /// written after the reconnect.
-pub struct Retry<Slave, Factory> {
-    slave: RefCell<Option<Slave>>,
-    factory: Factory,
-    strategy: NewStrategy,
-    initialized: Cell<bool>,
+pub struct Retry<Slave, Factory, FactoryError>
+where
+    Slave: Drain,
+    Slave::Err: Fail + Debug,
+    FactoryError: Fail + Debug,
+{
+    inner: Arc<Inner<Slave, Factory, FactoryError>>,
+    heartbeat: Option<Heartbeat>,
 }
 
-impl<Slave, FactoryError, Factory> Retry<Slave, Factory>
+impl<Slave, FactoryError, Factory> Retry<Slave, Factory, FactoryError>
 where
     Slave: Drain,
     FactoryError: Fail + Debug,
@@ -164,46 +485,155 @@ where
     ///   block (it uses the reconnect strategy provided) and it may return an error. If set to
     ///   `false`, the connection is made on the first logged message. No matter if connecting now
     ///   or later, the first connection attempt is without waiting.
-    pub fn new(factory: Factory, strategy: Option<NewStrategy>, connect_now: bool)
-        -> Result<Self, Error<FactoryError, Slave::Err>>
+    /// * `classifier`: An optional [classifier](type.Classifier.html) consulted on every error
+    ///   during a (re)connection attempt, letting it override the strategy's decision to retry
+    ///   (for example to give up early on an unrecoverable error). If `None`, every error is
+    ///   retried according to `strategy` alone.
+    pub fn new(
+        factory: Factory,
+        strategy: Option<NewStrategy>,
+        connect_now: bool,
+        classifier: Option<Classifier<FactoryError, Slave::Err>>,
+    ) -> Result<Self, Error<FactoryError, Slave::Err>>
     {
-        let result = Self {
-            slave: RefCell::new(None),
+        let inner = Inner {
+            slave: Mutex::new(None),
             factory,
-            strategy: strategy.unwrap_or_else(|| Box::new(|| default_new_strategy())),
-            initialized: Cell::new(false),
+            strategy: Mutex::new(strategy.unwrap_or_else(|| Box::new(|| default_new_strategy()))),
+            initialized: AtomicBool::new(false),
+            classifier: Mutex::new(classifier),
+            budget: None,
+            on_attempt: Mutex::new(None),
+            on_reconnect_success: Mutex::new(None),
+            on_give_up: Mutex::new(None),
+            liveness_probe: None,
         };
         if connect_now {
-            result.init(&mut result.slave.borrow_mut(), &mut (result.strategy)())
-                .map_err(|factory| Error { factory, slave: None })?;
+            let mut factory_attempt = 0;
+            let mut attempt_index = 0;
+            let init_result = inner.init(
+                &inner.slave,
+                &mut (inner.strategy.lock().unwrap())(),
+                &mut factory_attempt,
+                &mut attempt_index,
+            );
+            if let Err(factory) = init_result {
+                let err = Error { factory, slave: None };
+                inner.give_up(&err);
+                return Err(err);
+            }
         }
-        Ok(result)
+        Ok(Retry {
+            inner: Arc::new(inner),
+            heartbeat: None,
+        })
+    }
+    /// Returns a mutable reference to the inner state.
+    ///
+    /// Only available before the heartbeat thread (if any) has taken its own share, since the
+    /// builder methods are meant to be called right after
+    /// [`new`](#method.new) and before [`with_heartbeat`](#method.with_heartbeat).
+    fn inner_mut(&mut self) -> &mut Inner<Slave, Factory, FactoryError> {
+        Arc::get_mut(&mut self.inner)
+            .expect("Retry builder methods must be called before with_heartbeat")
+    }
+    /// Shares a [retry budget](struct.Budget.html) between reconnection attempts, capping how
+    /// many of them are made relative to successful log writes.
+    ///
+    /// The budget limits every connection attempt made after construction, whether started by a
+    /// logged record or by the heartbeat thread; it is set after construction, so it never blocks
+    /// the initial `connect_now` attempt.
+    pub fn with_budget(mut self, budget: Arc<Mutex<Budget>>) -> Self {
+        self.inner_mut().budget = Some(budget);
+        self
     }
-    fn init(&self, slave: &mut RefMut<Option<Slave>>, strategy: &mut Strategy)
-        -> Result<(), Option<FactoryError>>
+    /// Registers a hook called before every (re)connection attempt.
+    pub fn on_attempt<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(usize, Duration) + Send + 'static,
     {
-        let prefix: Strategy = if self.initialized.get() {
-            Box::new(iter::empty())
-        } else {
-            self.initialized.set(true);
-            Box::new(iter::once(Duration::from_secs(0)))
-        };
-        let mut last_err = None;
-        for sleep in prefix.chain(strategy) {
-            thread::sleep(sleep);
-            match (self.factory)() {
-                Ok(ok) => {
-                    **slave = Some(ok);
-                    return Ok(());
-                },
-                Err(err) => last_err = Some(err),
-            }
-        }
-        Err(last_err)
+        self.inner_mut().on_attempt = Mutex::new(Some(Box::new(hook)));
+        self
+    }
+    /// Registers a hook called when a reconnection succeeds.
+    pub fn on_reconnect_success<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(usize) + Send + 'static,
+    {
+        self.inner_mut().on_reconnect_success = Mutex::new(Some(Box::new(hook)));
+        self
+    }
+    /// Registers a hook called when the adapter gives up and returns the error to the caller.
+    pub fn on_give_up<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&Error<FactoryError, Slave::Err>) + Send + 'static,
+    {
+        self.inner_mut().on_give_up = Mutex::new(Some(Box::new(hook)));
+        self
+    }
+    /// Gives [`with_heartbeat`](#method.with_heartbeat) a way to tell a dead slave apart from an
+    /// idle one.
+    ///
+    /// Without a probe, the heartbeat thread can only reconnect a slave that a previous `log` call
+    /// has already observed failing; it has no generic way to notice one that died without being
+    /// used. Providing one lets it check the slave currently in use (for example by inspecting the
+    /// underlying socket) and reconnect it proactively when the check fails.
+    pub fn with_liveness_probe<F>(mut self, probe: F) -> Self
+    where
+        F: Fn(&Slave) -> bool + Send + Sync + 'static,
+    {
+        self.inner_mut().liveness_probe = Some(Box::new(probe));
+        self
+    }
+}
+
+impl<Slave, FactoryError, Factory> Retry<Slave, Factory, FactoryError>
+where
+    Slave: Drain + Send + 'static,
+    Slave::Err: Fail + Debug + Send + Sync + 'static,
+    FactoryError: Fail + Debug + Send + Sync + 'static,
+    Factory: Fn() -> Result<Slave, FactoryError> + Send + Sync + 'static,
+{
+    /// Spawns a background thread that proactively reconnects a dead drain.
+    ///
+    /// Without this, a drain that died during an idle period is only discovered (and only
+    /// reconnected) on the next call to `log`, which then blocks through the whole reconnect. With
+    /// a heartbeat, the reconnection happens on this background thread roughly every `interval`
+    /// instead, so the next `log` call is more likely to find a drain ready to go.
+    ///
+    /// Call [`with_liveness_probe`](#method.with_liveness_probe) first if you want the heartbeat
+    /// to catch a drain that died silently, without anything having tried to use it yet. Without
+    /// a probe, the heartbeat thread can only reconnect a drain that a `log` call already observed
+    /// failing, which is a much narrower win: it still keeps the reconnect off that particular
+    /// `log` call, but only once something has already hit the failure once.
+    ///
+    /// Call this last, after [`with_budget`](#method.with_budget), [`with_liveness_probe`]
+    /// (#method.with_liveness_probe) and the `on_*` hooks: once the heartbeat thread is running,
+    /// those builder methods can no longer get exclusive access to the shared state and will
+    /// panic.
+    ///
+    /// The thread is asked to stop and joined when the returned `Retry` is dropped.
+    pub fn with_heartbeat(mut self, interval: Duration) -> Self {
+        let inner = Arc::clone(&self.inner);
+        let (shutdown, shutdown_rx) = mpsc::channel();
+        let handle = thread::Builder::new()
+            .name("slog-retry-heartbeat".to_owned())
+            .spawn(move || loop {
+                match shutdown_rx.recv_timeout(interval) {
+                    Ok(()) | Err(RecvTimeoutError::Disconnected) => break,
+                    Err(RecvTimeoutError::Timeout) => inner.heartbeat_tick(),
+                }
+            })
+            .expect("failed to spawn the slog-retry heartbeat thread");
+        self.heartbeat = Some(Heartbeat {
+            shutdown: Some(shutdown),
+            handle: Some(handle),
+        });
+        self
     }
 }
 
-impl<Slave, FactoryError, Factory> Drain for Retry<Slave, Factory>
+impl<Slave, FactoryError, Factory> Drain for Retry<Slave, Factory, FactoryError>
 where
     Slave: Drain,
     FactoryError: Fail + Debug,
@@ -213,32 +643,7 @@ where
     type Ok = Slave::Ok;
     type Err = Error<FactoryError, Slave::Err>;
     fn log(&self, record: &Record, values: &OwnedKVList) -> Result<Self::Ok, Self::Err> {
-        let mut borrowed = self.slave.borrow_mut();
-        let mut slave_err = None;
-
-        if let Some(ref slave) = *borrowed {
-            match slave.log(record, values) {
-                Ok(ok) => return Ok(ok),
-                Err(err) => slave_err = Some(err),
-            }
-        }
-        // By now there was no slave to start with or it failed, so we recreate it.
-        borrowed.take();
-
-        // Try creating a new one and retry with that.
-        let mut strategy = (self.strategy)();
-        loop {
-            match self.init(&mut borrowed, &mut strategy) {
-                Err(factory) => return Err(Error {
-                    factory,
-                    slave: slave_err,
-                }),
-                Ok(()) => match borrowed.as_ref().unwrap().log(record, values) {
-                    Ok(ok) => return Ok(ok),
-                    Err(err) => slave_err = Some(err),
-                },
-            }
-        }
+        self.inner.log(record, values)
     }
 }
 
@@ -15,7 +15,7 @@ use std::thread;
 use std::time::Duration;
 
 use slog::{Drain, Logger, OwnedKVList, Record};
-use slog_retry::{NewStrategy, Retry};
+use slog_retry::{Classifier, NewStrategy, Retry, RetryAction, SlaveOrFactoryError};
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 enum Action {
@@ -126,7 +126,7 @@ fn factory(scenario: &Arc<Scenario>) -> Result<FailLogger, CreateError> {
 }
 
 fn logger(scenario: Arc<Scenario>, strategy: Option<NewStrategy>, connect_now: bool) -> Logger {
-    let retry = Retry::new(move || factory(&scenario), strategy, connect_now)
+    let retry = Retry::new(move || factory(&scenario), strategy, connect_now, None)
         .unwrap()
         .ignore_res();
     Logger::root(Mutex::new(retry).fuse(), o!())
@@ -198,7 +198,7 @@ fn retries() {
 #[test]
 fn give_up_initial() {
     let scenario = Scenario::new(vec![FactoryError, FactoryError, FactoryError]);
-    assert!(Retry::new(move || factory(&scenario), strategy(2), true).is_err());
+    assert!(Retry::new(move || factory(&scenario), strategy(2), true, None).is_err());
 }
 
 /// Give up when logging. But try again on the next message.
@@ -241,3 +241,148 @@ fn give_up_delayed() {
     info!(root, "Failed message");
     info!(root, "Successful message");
 }
+
+/// A classifier that gives up right away overrides the strategy and stops further attempts.
+#[test]
+fn classifier_give_up_now() {
+    let scenario = Scenario::new(vec![FactoryError]);
+    let classifier: Classifier<CreateError, LoggerError> = Box::new(|_, _| RetryAction::GiveUpNow);
+    let result = Retry::new(move || factory(&scenario), strategy(5), true, Some(classifier));
+    assert!(result.is_err());
+}
+
+/// A classifier that forwards a slave error right away skips the rest of the strategy too.
+#[test]
+fn classifier_forward_immediately() {
+    let scenario = Scenario::new(vec![
+        // Initial connect
+        FactorySuccess,
+        // Fails once, and the classifier forwards that instead of retrying
+        LogError,
+    ]);
+    let classifier: Classifier<CreateError, LoggerError> = Box::new(|err, _| match *err {
+        SlaveOrFactoryError::Slave(_) => RetryAction::ForwardImmediately,
+        SlaveOrFactoryError::Factory(_) => RetryAction::Retry,
+    });
+    let retry = Retry::new(move || factory(&scenario), strategy(5), true, Some(classifier))
+        .unwrap()
+        .ignore_res();
+    let root = Logger::root(Mutex::new(retry).fuse(), o!());
+    info!(root, "Msg");
+}
+
+/// A classifier consulted on every error, the first one included, can override the wait without
+/// changing how many attempts the strategy allows.
+#[test]
+fn classifier_retry_after_override() {
+    let scenario = Scenario::new(vec![FactoryError, FactoryError, FactorySuccess]);
+    let consulted = Arc::new(Mutex::new(Vec::new()));
+    let consulted_classifier = Arc::clone(&consulted);
+    let classifier: Classifier<CreateError, LoggerError> = Box::new(move |err, attempt| {
+        if let SlaveOrFactoryError::Factory(_) = *err {
+            consulted_classifier.lock().unwrap().push(attempt);
+        }
+        RetryAction::RetryAfter(Duration::from_secs(0))
+    });
+    let result = Retry::new(move || factory(&scenario), strategy(2), true, Some(classifier));
+    assert!(result.is_ok());
+    assert_eq!(*consulted.lock().unwrap(), vec![1, 2]);
+}
+
+/// `on_attempt` counts every attempt, including the first, across the whole reconnect, and
+/// `on_reconnect_success` never fires for the very first connection.
+#[test]
+fn on_attempt_counts_every_try() {
+    let scenario = Scenario::new(vec![
+        FactoryError,
+        FactoryError,
+        FactorySuccess,
+        LogSuccess,
+    ]);
+    let attempts = Arc::new(Mutex::new(Vec::new()));
+    let attempts_hook = Arc::clone(&attempts);
+    let reconnects = Arc::new(Mutex::new(Vec::new()));
+    let reconnects_hook = Arc::clone(&reconnects);
+    let retry = Retry::new(move || factory(&scenario), strategy(5), false, None)
+        .unwrap()
+        .on_attempt(move |attempt, _delay| attempts_hook.lock().unwrap().push(attempt))
+        .on_reconnect_success(move |attempt| reconnects_hook.lock().unwrap().push(attempt))
+        .ignore_res();
+    let root = Logger::root(Mutex::new(retry).fuse(), o!());
+    info!(root, "Msg");
+    assert_eq!(*attempts.lock().unwrap(), vec![1, 2, 3]);
+    assert!(reconnects.lock().unwrap().is_empty());
+}
+
+/// `on_reconnect_success` fires, with the attempt it took, for an actual reconnect (as opposed to
+/// the very first connection).
+#[test]
+fn on_reconnect_success_fires_for_real_reconnects() {
+    let scenario = Scenario::new(vec![
+        // Initial connect
+        FactorySuccess,
+        // Fails once, then reconnects on the second attempt
+        LogError,
+        FactoryError,
+        FactorySuccess,
+        LogSuccess,
+    ]);
+    let reconnects = Arc::new(Mutex::new(Vec::new()));
+    let reconnects_hook = Arc::clone(&reconnects);
+    let gave_up = Arc::new(Mutex::new(false));
+    let gave_up_hook = Arc::clone(&gave_up);
+    let retry = Retry::new(move || factory(&scenario), strategy(5), true, None)
+        .unwrap()
+        .on_reconnect_success(move |attempt| reconnects_hook.lock().unwrap().push(attempt))
+        .on_give_up(move |_| *gave_up_hook.lock().unwrap() = true)
+        .ignore_res();
+    let root = Logger::root(Mutex::new(retry).fuse(), o!());
+    info!(root, "Msg");
+    assert_eq!(*reconnects.lock().unwrap(), vec![2]);
+    assert!(!*gave_up.lock().unwrap());
+}
+
+/// `on_give_up` fires once the adapter has exhausted the strategy and is about to report the
+/// error back to the caller.
+#[test]
+fn on_give_up_fires() {
+    let scenario = Scenario::new(vec![FactoryError, FactoryError, FactoryError]);
+    let gave_up = Arc::new(Mutex::new(false));
+    let gave_up_hook = Arc::clone(&gave_up);
+    let retry = Retry::new(move || factory(&scenario), strategy(2), false, None)
+        .unwrap()
+        .on_give_up(move |_| *gave_up_hook.lock().unwrap() = true)
+        .ignore_res();
+    let root = Logger::root(Mutex::new(retry).fuse(), o!());
+    info!(root, "Msg");
+    assert!(*gave_up.lock().unwrap());
+}
+
+/// The heartbeat thread reconnects a slave that a liveness probe reports as dead, without
+/// waiting for a `log` call to discover it the hard way.
+#[test]
+fn heartbeat_reconnects_via_liveness_probe() {
+    let scenario = Scenario::new(vec![
+        // Initial connect
+        FactorySuccess,
+        // The heartbeat thread finds the slave dead once and reconnects it
+        FactorySuccess,
+    ]);
+    let probe_calls = Arc::new(Mutex::new(0usize));
+    let probe_calls_hook = Arc::clone(&probe_calls);
+    let retry = Retry::new(move || factory(&scenario), strategy(1), true, None)
+        .unwrap()
+        .with_liveness_probe(move |_slave| {
+            // Report dead on the very first check only, so exactly one reconnect happens no
+            // matter how many times the heartbeat thread ticks afterwards.
+            let mut calls = probe_calls_hook.lock().unwrap();
+            let seen_before = *calls > 0;
+            *calls += 1;
+            seen_before
+        })
+        .with_heartbeat(Duration::from_millis(5));
+    // Give the background thread a generous number of chances to tick before we check.
+    thread::sleep(Duration::from_millis(150));
+    // Dropping joins the heartbeat thread, shutting it down cleanly.
+    drop(retry);
+}